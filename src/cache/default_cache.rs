@@ -1,18 +1,45 @@
-use crate::cache::Cache;
+use crate::cache::{CanExpire, Cache, CacheStats, InternalMemoryCache, LfuCache, LruCache};
 
 use async_trait::async_trait;
 use ttl_cache::TtlCache;
 
 use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
+/// Selects the eviction strategy backing a `DefaultCache`'s capacity
+/// bound. `Ttl` is the original behavior (evicts by insertion order once
+/// entries expire or capacity is exceeded); `Lru` and `Lfu` swap in
+/// recency/frequency-aware adapters so hot entries survive eviction
+/// under skewed access patterns.
+#[derive(Clone, Copy)]
+pub enum EvictionPolicy {
+    Ttl,
+    Lru,
+    Lfu,
+}
+
+enum Storage<K, V>
+where
+    K: Eq + Hash + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    Ttl(TtlCache<K, V>),
+    Lru(LruCache<K, V>),
+    Lfu(LfuCache<K, V>),
+}
+
 pub struct DefaultCache<K, V>
 where
     K: Eq + Hash + Send + Sync + 'static,
     V: Send + Sync + 'static,
 {
     pub ttl: Duration,
-    cache: TtlCache<K, V>,
+    storage: Storage<K, V>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    inserts: AtomicU64,
+    evictions: AtomicU64,
 }
 
 impl<K, V> DefaultCache<K, V>
@@ -21,10 +48,62 @@ where
     V: Send + Sync + 'static,
 {
     pub fn new(cap: usize) -> Box<dyn Cache<K, V>> {
-        Box::new(DefaultCache {
+        Box::new(DefaultCache::build(cap, EvictionPolicy::Ttl))
+    }
+
+    pub fn with_policy(cap: usize, policy: EvictionPolicy) -> Box<dyn Cache<K, V>> {
+        Box::new(DefaultCache::build(cap, policy))
+    }
+
+    /// Builds the concrete cache rather than a `Box<dyn Cache<K, V>>`.
+    /// Most callers want `new`/`with_policy`, but going through a
+    /// trait object erases `DefaultCache`'s inherent methods, so code
+    /// that needs `get_if_fresh` (which requires `V: CanExpire`, not
+    /// expressible on the object-safe `Cache` trait) should build the
+    /// concrete type directly with this instead.
+    pub fn build(cap: usize, policy: EvictionPolicy) -> Self {
+        let storage = match policy {
+            EvictionPolicy::Ttl => Storage::Ttl(TtlCache::new(cap)),
+            EvictionPolicy::Lru => Storage::Lru(LruCache::new(cap)),
+            EvictionPolicy::Lfu => Storage::Lfu(LfuCache::new(cap)),
+        };
+        DefaultCache {
             ttl: Duration::from_secs(120),
-            cache: TtlCache::new(cap),
-        }) as Box<dyn Cache<K, V>>
+            storage,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            inserts: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Shared by `set` (cache-wide `self.ttl`) and `set_with_ttl` (a
+    /// per-entry override); the `Lru`/`Lfu` policies don't expire
+    /// entries on their own schedule yet, so `ttl` only affects `Ttl`.
+    fn insert(&mut self, k: K, v: V, ttl: Duration) {
+        self.inserts.fetch_add(1, Ordering::Relaxed);
+        match &mut self.storage {
+            Storage::Ttl(cache) => {
+                let at_capacity = cache.len() >= cache.capacity() && !cache.contains_key(&k);
+                if cache.contains_key(&k) {
+                    cache.remove(&k);
+                }
+                cache.insert(k, v, ttl);
+                if at_capacity {
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            Storage::Lru(cache) => {
+                if cache.push(k, v).is_some() {
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            Storage::Lfu(cache) => {
+                if cache.push(k, v).is_some() {
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
     }
 }
 
@@ -34,8 +113,29 @@ where
     K: Eq + Hash + Send + Sync + 'static,
     V: Send + Sync + 'static,
 {
+    /// Shrinking capacity can pop entries on its own, independent of
+    /// `insert`'s push path, so account for them here too rather than
+    /// only counting evictions triggered by `set`/`set_with_ttl` — other-
+    /// wise `stats().evictions` would undercount whenever a caller tunes
+    /// capacity down on a live cache.
     fn set_capacity(&mut self, cap: usize) {
-        self.cache.set_capacity(cap);
+        let before = match &self.storage {
+            Storage::Ttl(cache) => cache.len(),
+            Storage::Lru(cache) => cache.len(),
+            Storage::Lfu(cache) => cache.len(),
+        };
+        match &mut self.storage {
+            Storage::Ttl(cache) => cache.set_capacity(cap),
+            Storage::Lru(cache) => cache.set_capacity(cap),
+            Storage::Lfu(cache) => cache.set_capacity(cap),
+        }
+        let after = match &self.storage {
+            Storage::Ttl(cache) => cache.len(),
+            Storage::Lru(cache) => cache.len(),
+            Storage::Lfu(cache) => cache.len(),
+        };
+        self.evictions
+            .fetch_add(before.saturating_sub(after) as u64, Ordering::Relaxed);
     }
 
     fn set_ttl(&mut self, ttl: Duration) {
@@ -43,22 +143,67 @@ where
     }
 
     async fn get<'a>(&'a self, k: &K) -> Option<&'a V> {
-        self.cache.get(k)
+        let found = match &self.storage {
+            Storage::Ttl(cache) => cache.get(k),
+            Storage::Lru(cache) => cache.get(k),
+            Storage::Lfu(cache) => cache.get(k),
+        };
+        if found.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        found
     }
 
     async fn has(&self, k: &K) -> bool {
-        self.cache.contains_key(k)
+        match &self.storage {
+            Storage::Ttl(cache) => cache.contains_key(k),
+            Storage::Lru(cache) => cache.contains_key(k),
+            Storage::Lfu(cache) => cache.contains_key(k),
+        }
     }
 
     async fn set(&mut self, k: K, v: V) {
-        if self.has(&k).await {
-            self.cache.remove(&k);
-        }
-        self.cache.insert(k, v, self.ttl);
+        let ttl = self.ttl;
+        self.insert(k, v, ttl);
+    }
+
+    async fn set_with_ttl(&mut self, k: K, v: V, ttl: Duration) {
+        self.insert(k, v, ttl);
     }
 
     async fn clear(&mut self) {
-        self.cache.clear();
+        match &mut self.storage {
+            Storage::Ttl(cache) => cache.clear(),
+            Storage::Lru(cache) => cache.clear(),
+            Storage::Lfu(cache) => cache.clear(),
+        }
+    }
+
+    async fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            inserts: self.inserts.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<K, V> DefaultCache<K, V>
+where
+    K: Eq + Hash + Send + Sync + 'static,
+    V: CanExpire + Send + Sync + 'static,
+{
+    /// Like `Cache::get`, but also treats a value as absent once it
+    /// reports itself expired via `CanExpire`, independent of the
+    /// cache-wide or per-entry wall-clock TTL.
+    pub async fn get_if_fresh<'a>(&'a self, k: &K) -> Option<&'a V> {
+        match Cache::get(self, k).await {
+            Some(v) if !v.is_expired() => Some(v),
+            _ => None,
+        }
     }
 }
 
@@ -122,4 +267,149 @@ mod tests {
             assert!(cache.has(&vec!["unknow", "/data3", "read_write"]).await);
         });
     }
+
+    #[test]
+    fn test_lru_policy_keeps_hot_entry() {
+        let mut cache = DefaultCache::with_policy(2, EvictionPolicy::Lru);
+
+        task::block_on(async move {
+            cache.set(vec!["alice", "/data1", "read"], false).await;
+            cache.set(vec!["bob", "/data2", "write"], false).await;
+            // Touch alice so bob becomes the least-recently-used entry.
+            assert!(cache.get(&vec!["alice", "/data1", "read"]).await.is_some());
+            cache
+                .set(vec!["carol", "/data3", "read_write"], false)
+                .await;
+            assert!(cache.has(&vec!["alice", "/data1", "read"]).await);
+            assert!(!cache.has(&vec!["bob", "/data2", "write"]).await);
+            assert!(cache.has(&vec!["carol", "/data3", "read_write"]).await);
+        });
+    }
+
+    #[test]
+    fn test_lfu_policy_keeps_hot_entry() {
+        let mut cache = DefaultCache::with_policy(2, EvictionPolicy::Lfu);
+
+        task::block_on(async move {
+            cache.set(vec!["alice", "/data1", "read"], false).await;
+            cache.set(vec!["bob", "/data2", "write"], false).await;
+            // Hit alice repeatedly so it outlives bob once capacity is exceeded.
+            for _ in 0..5 {
+                assert!(cache.get(&vec!["alice", "/data1", "read"]).await.is_some());
+            }
+            cache
+                .set(vec!["carol", "/data3", "read_write"], false)
+                .await;
+            assert!(cache.has(&vec!["alice", "/data1", "read"]).await);
+            assert!(!cache.has(&vec!["bob", "/data2", "write"]).await);
+            assert!(cache.has(&vec!["carol", "/data3", "read_write"]).await);
+        });
+    }
+
+    #[test]
+    fn test_set_capacity_shrink_counts_as_eviction() {
+        let mut cache = DefaultCache::with_policy(2, EvictionPolicy::Lru);
+
+        task::block_on(async move {
+            cache.set(vec!["alice", "/data1", "read"], false).await;
+            cache.set(vec!["bob", "/data2", "write"], false).await;
+            assert_eq!(cache.stats().await.evictions, 0);
+
+            cache.set_capacity(1);
+            assert_eq!(cache.stats().await.evictions, 1);
+            assert!(!cache.has(&vec!["alice", "/data1", "read"]).await);
+            assert!(cache.has(&vec!["bob", "/data2", "write"]).await);
+        });
+    }
+
+    #[test]
+    fn test_lfu_policy_breaks_frequency_ties_by_insertion_order() {
+        let mut cache = DefaultCache::with_policy(2, EvictionPolicy::Lfu);
+
+        task::block_on(async move {
+            // Neither key is ever read back, so both sit at the same
+            // (zero) frequency when capacity is exceeded — the tie must
+            // be broken by insertion order (oldest first), not whatever
+            // order HashMap happens to iterate in.
+            cache.set(vec!["alice", "/data1", "read"], false).await;
+            cache.set(vec!["bob", "/data2", "write"], false).await;
+            cache
+                .set(vec!["carol", "/data3", "read_write"], false)
+                .await;
+
+            assert!(!cache.has(&vec!["alice", "/data1", "read"]).await);
+            assert!(cache.has(&vec!["bob", "/data2", "write"]).await);
+            assert!(cache.has(&vec!["carol", "/data3", "read_write"]).await);
+        });
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut cache = DefaultCache::new(1);
+
+        task::block_on(async move {
+            cache.set(vec!["alice", "/data1", "read"], false).await;
+            assert!(cache.get(&vec!["alice", "/data1", "read"]).await.is_some());
+            assert!(cache.get(&vec!["bob", "/data2", "write"]).await.is_none());
+            cache.set(vec!["bob", "/data2", "write"], false).await;
+
+            let stats = cache.stats().await;
+            assert_eq!(stats.hits, 1);
+            assert_eq!(stats.misses, 1);
+            assert_eq!(stats.inserts, 2);
+            assert_eq!(stats.evictions, 1);
+        });
+    }
+
+    #[test]
+    fn test_set_with_ttl_overrides_global_ttl() {
+        let mut cache = DefaultCache::new(2);
+        cache.set_ttl(Duration::from_secs(120));
+
+        task::block_on(async move {
+            cache
+                .set_with_ttl(vec!["alice", "/data1", "read"], false, Duration::from_secs(1))
+                .await;
+            cache.set(vec!["bob", "/data2", "write"], false).await;
+
+            sleep(Duration::from_secs(2));
+            assert!(!cache.has(&vec!["alice", "/data1", "read"]).await);
+            assert!(cache.has(&vec!["bob", "/data2", "write"]).await);
+        });
+    }
+
+    #[derive(PartialEq, Debug)]
+    struct ExpiringDecision {
+        allowed: bool,
+        expired: bool,
+    }
+
+    impl CanExpire for ExpiringDecision {
+        fn is_expired(&self) -> bool {
+            self.expired
+        }
+    }
+
+    #[test]
+    fn test_get_if_fresh_honors_self_reported_expiry() {
+        let mut cache = DefaultCache::build(1, EvictionPolicy::Ttl);
+
+        task::block_on(async move {
+            cache
+                .set(
+                    vec!["alice", "/data1", "read"],
+                    ExpiringDecision {
+                        allowed: true,
+                        expired: true,
+                    },
+                )
+                .await;
+
+            assert!(cache.get(&vec!["alice", "/data1", "read"]).await.is_some());
+            assert!(cache
+                .get_if_fresh(&vec!["alice", "/data1", "read"])
+                .await
+                .is_none());
+        });
+    }
 }
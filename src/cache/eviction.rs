@@ -0,0 +1,39 @@
+use std::hash::Hash;
+
+/// Adapter over a bounded in-memory eviction strategy, modeled after
+/// mangadex-home's `InternalMemoryCache` so `DefaultCache` can swap the
+/// policy backing its capacity eviction without changing its own API.
+///
+/// `get` takes `&self` (not `&mut self`) so it matches `Cache::get`'s
+/// signature; recency/frequency bookkeeping needed by a given policy is
+/// expected to use interior mutability internally.
+pub trait InternalMemoryCache<K, V>: Send + Sync
+where
+    K: Eq + Hash,
+{
+    /// Creates a cache with no fixed capacity limit.
+    fn unbounded() -> Self;
+
+    fn set_capacity(&mut self, cap: usize);
+
+    fn contains_key(&self, k: &K) -> bool;
+
+    fn get(&self, k: &K) -> Option<&V>;
+
+    fn remove(&mut self, k: &K) -> Option<V>;
+
+    /// Inserts `k`/`v`, evicting and returning an entry if this push put
+    /// the cache over capacity.
+    fn push(&mut self, k: K, v: V) -> Option<(K, V)>;
+
+    /// Evicts and returns the next victim according to this policy.
+    fn pop(&mut self) -> Option<(K, V)>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn clear(&mut self);
+}
@@ -0,0 +1,59 @@
+use crate::cache::{Cache, DefaultCache, EvictionPolicy};
+
+use std::hash::Hash;
+
+/// Produces a boxed `Cache<K, V>`, mirroring async-graphql's DataLoader
+/// design: a `CachedEnforcer` that's handed a `CacheFactory` at
+/// construction time isn't implicitly tied to one cache constructor, so
+/// callers can plug in `RedisCache`, a `TinyLfuCache`, or their own
+/// implementation without touching enforcer code.
+pub trait CacheFactory: Send + Sync {
+    fn create<K, V>(&self) -> Box<dyn Cache<K, V>>
+    where
+        K: Eq + Hash + Send + Sync + 'static,
+        V: Send + Sync + 'static;
+}
+
+/// The factory `CachedEnforcer` falls back to when none is supplied:
+/// an in-memory `DefaultCache` under the given capacity and eviction
+/// policy.
+pub struct DefaultCacheFactory {
+    pub capacity: usize,
+    pub policy: EvictionPolicy,
+}
+
+impl DefaultCacheFactory {
+    pub fn new(capacity: usize) -> Self {
+        DefaultCacheFactory {
+            capacity,
+            policy: EvictionPolicy::Ttl,
+        }
+    }
+}
+
+impl CacheFactory for DefaultCacheFactory {
+    fn create<K, V>(&self) -> Box<dyn Cache<K, V>>
+    where
+        K: Eq + Hash + Send + Sync + 'static,
+        V: Send + Sync + 'static,
+    {
+        DefaultCache::with_policy(self.capacity, self.policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task;
+
+    #[test]
+    fn test_default_cache_factory_creates_usable_cache() {
+        let factory = DefaultCacheFactory::new(1);
+        let mut cache: Box<dyn Cache<Vec<&str>, bool>> = factory.create();
+
+        task::block_on(async move {
+            cache.set(vec!["alice", "/data1", "read"], false).await;
+            assert!(cache.get(&vec!["alice", "/data1", "read"]).await == Some(&false));
+        });
+    }
+}
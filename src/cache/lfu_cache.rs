@@ -0,0 +1,121 @@
+use crate::cache::eviction::InternalMemoryCache;
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Least-frequently-used eviction policy: the key with the lowest access
+/// count is evicted first, so hot keys survive capacity pressure even if
+/// they were inserted a long time ago. Ties are broken by insertion
+/// order (oldest first) rather than `HashMap`'s unspecified iteration
+/// order, so eviction is deterministic.
+pub struct LfuCache<K, V> {
+    cap: usize,
+    values: HashMap<K, V>,
+    freq: HashMap<K, AtomicU64>,
+    // Oldest-inserted first; used only to break frequency ties.
+    order: VecDeque<K>,
+}
+
+impl<K, V> LfuCache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new(cap: usize) -> Self {
+        LfuCache {
+            cap,
+            values: HashMap::new(),
+            freq: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn least_frequent_key(&self) -> Option<K> {
+        self.order
+            .iter()
+            .min_by_key(|k| {
+                self.freq
+                    .get(*k)
+                    .map(|c| c.load(Ordering::Relaxed))
+                    .unwrap_or(0)
+            })
+            .cloned()
+    }
+}
+
+impl<K, V> InternalMemoryCache<K, V> for LfuCache<K, V>
+where
+    K: Eq + Hash + Clone + Send + Sync,
+    V: Send + Sync,
+{
+    fn unbounded() -> Self {
+        LfuCache::new(usize::MAX)
+    }
+
+    fn set_capacity(&mut self, cap: usize) {
+        self.cap = cap;
+        while self.values.len() > self.cap {
+            self.pop();
+        }
+    }
+
+    fn contains_key(&self, k: &K) -> bool {
+        self.values.contains_key(k)
+    }
+
+    fn get(&self, k: &K) -> Option<&V> {
+        let v = self.values.get(k)?;
+        if let Some(count) = self.freq.get(k) {
+            count.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(v)
+    }
+
+    fn remove(&mut self, k: &K) -> Option<V> {
+        self.freq.remove(k);
+        if let Some(pos) = self.order.iter().position(|x| x == k) {
+            self.order.remove(pos);
+        }
+        self.values.remove(k)
+    }
+
+    fn push(&mut self, k: K, v: V) -> Option<(K, V)> {
+        let prior_count = self
+            .freq
+            .get(&k)
+            .map(|c| c.load(Ordering::Relaxed))
+            .unwrap_or(0);
+        if let Some(pos) = self.order.iter().position(|x| x == &k) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(k.clone());
+        self.values.insert(k.clone(), v);
+        self.freq.insert(k, AtomicU64::new(prior_count));
+
+        if self.values.len() > self.cap {
+            self.pop()
+        } else {
+            None
+        }
+    }
+
+    fn pop(&mut self) -> Option<(K, V)> {
+        let k = self.least_frequent_key()?;
+        self.freq.remove(&k);
+        if let Some(pos) = self.order.iter().position(|x| x == &k) {
+            self.order.remove(pos);
+        }
+        let v = self.values.remove(&k)?;
+        Some((k, v))
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+        self.freq.clear();
+        self.order.clear();
+    }
+}
@@ -0,0 +1,100 @@
+use crate::cache::eviction::InternalMemoryCache;
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::Mutex;
+
+/// Least-recently-used eviction policy: the key that hasn't been touched
+/// (via `get` or `push`) for the longest time is evicted first.
+pub struct LruCache<K, V> {
+    cap: usize,
+    map: HashMap<K, V>,
+    // Front is least-recently-used, back is most-recently-used.
+    order: Mutex<VecDeque<K>>,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new(cap: usize) -> Self {
+        LruCache {
+            cap,
+            map: HashMap::new(),
+            order: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn touch(&self, k: &K) {
+        let mut order = self.order.lock().unwrap();
+        if let Some(pos) = order.iter().position(|x| x == k) {
+            let k = order.remove(pos).unwrap();
+            order.push_back(k);
+        }
+    }
+}
+
+impl<K, V> InternalMemoryCache<K, V> for LruCache<K, V>
+where
+    K: Eq + Hash + Clone + Send + Sync,
+    V: Send + Sync,
+{
+    fn unbounded() -> Self {
+        LruCache::new(usize::MAX)
+    }
+
+    fn set_capacity(&mut self, cap: usize) {
+        self.cap = cap;
+        while self.map.len() > self.cap {
+            self.pop();
+        }
+    }
+
+    fn contains_key(&self, k: &K) -> bool {
+        self.map.contains_key(k)
+    }
+
+    fn get(&self, k: &K) -> Option<&V> {
+        let v = self.map.get(k)?;
+        self.touch(k);
+        Some(v)
+    }
+
+    fn remove(&mut self, k: &K) -> Option<V> {
+        let mut order = self.order.lock().unwrap();
+        if let Some(pos) = order.iter().position(|x| x == k) {
+            order.remove(pos);
+        }
+        drop(order);
+        self.map.remove(k)
+    }
+
+    fn push(&mut self, k: K, v: V) -> Option<(K, V)> {
+        if self.map.contains_key(&k) {
+            self.remove(&k);
+        }
+        self.map.insert(k.clone(), v);
+        self.order.lock().unwrap().push_back(k);
+
+        if self.map.len() > self.cap {
+            self.pop()
+        } else {
+            None
+        }
+    }
+
+    fn pop(&mut self) -> Option<(K, V)> {
+        let k = self.order.lock().unwrap().pop_front()?;
+        let v = self.map.remove(&k)?;
+        Some((k, v))
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.order.lock().unwrap().clear();
+    }
+}
@@ -0,0 +1,97 @@
+//! Decision cache used by `CachedEnforcer` to avoid re-evaluating the
+//! policy model for repeated `enforce` calls with the same request.
+
+mod default_cache;
+mod eviction;
+mod factory;
+mod lfu_cache;
+mod lru_cache;
+#[cfg(feature = "redis_cache")]
+mod redis_cache;
+mod tiny_lfu_cache;
+
+pub use default_cache::{DefaultCache, EvictionPolicy};
+pub use eviction::InternalMemoryCache;
+pub use factory::{CacheFactory, DefaultCacheFactory};
+pub use lfu_cache::LfuCache;
+pub use lru_cache::LruCache;
+#[cfg(feature = "redis_cache")]
+pub use redis_cache::RedisCache;
+pub use tiny_lfu_cache::TinyLfuCache;
+
+use async_trait::async_trait;
+
+use std::hash::Hash;
+use std::time::Duration;
+
+/// Point-in-time hit/miss/eviction counters for a `Cache` implementation,
+/// analogous to the `cached` crate's `cache_hits()`/`cache_misses()`, so
+/// operators can tune enforcer cache capacity/TTL from real hit ratios
+/// instead of guessing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub inserts: u64,
+    pub evictions: u64,
+}
+
+#[async_trait]
+pub trait Cache<K: Eq + Hash + Send + Sync + 'static, V: Send + Sync + 'static>:
+    Send + Sync
+{
+    fn set_capacity(&mut self, cap: usize);
+
+    fn set_ttl(&mut self, ttl: Duration);
+
+    /// Returns a reference borrowed from `&self`. Some implementations
+    /// (e.g. `RedisCache`) synthesize that reference from shared,
+    /// lock-guarded state that a `&mut self` method can later free,
+    /// relying on the borrow checker to forbid such a call while this
+    /// borrow is alive — but `Cache: Send + Sync` also allows a caller to
+    /// hold `&Self` across threads with no lock of its own, which is the
+    /// only thing that actually provides that exclusion. Callers driving
+    /// such an implementation (e.g. `CachedEnforcer`) MUST ensure no
+    /// `&mut self` call (`set`, `clear`, `set_capacity`, ...) ever runs
+    /// concurrently with a borrow still live from this method — in
+    /// practice, by holding the `Box<dyn Cache<K, V>>` behind a
+    /// single-writer lock (such as `RwLock`) for as long as the borrow is
+    /// in use.
+    async fn get<'a>(&'a self, k: &K) -> Option<&'a V>;
+
+    async fn has(&self, k: &K) -> bool;
+
+    async fn set(&mut self, k: K, v: V);
+
+    async fn clear(&mut self);
+
+    async fn stats(&self) -> CacheStats;
+
+    /// Like `set`, but lets implementations that track a cost budget
+    /// (e.g. `TinyLfuCache`) weigh this entry against others when
+    /// deciding what to evict. Implementations that don't track cost
+    /// treat every entry as equally weighted, so the default just
+    /// forwards to `set`.
+    async fn set_with_cost(&mut self, k: K, v: V, cost: u64) {
+        let _ = cost;
+        self.set(k, v).await;
+    }
+
+    /// Like `set`, but expires `k` on its own schedule instead of the
+    /// cache-wide TTL set via `set_ttl` — e.g. a short TTL for a deny
+    /// decision that might soon be granted, and a longer one for a
+    /// stable allow. Implementations that only support one global TTL
+    /// fall back to `set` and ignore the override.
+    async fn set_with_ttl(&mut self, k: K, v: V, ttl: Duration) {
+        let _ = ttl;
+        self.set(k, v).await;
+    }
+}
+
+/// Optional companion to wall-clock TTL: a cached value can declare
+/// itself expired independent of how long it's been cached (e.g. a
+/// decision that should be re-checked once some external condition
+/// changes), mirroring the `cached` crate's `CanExpire`.
+pub trait CanExpire {
+    fn is_expired(&self) -> bool;
+}
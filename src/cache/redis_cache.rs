@@ -0,0 +1,345 @@
+use crate::cache::{Cache, CacheStats};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use redis::AsyncCommands;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Distributed decision cache backed by Redis, following mirror-cache
+/// and mangadex-home's Redis-backed cache pattern. Several enforcer
+/// processes behind a load balancer share the same Redis keyspace, so a
+/// `clear()` triggered by `load_policy`/`update_policy` on any one of
+/// them is published on `invalidation_channel`; every instance
+/// subscribed to that channel stops serving its mirror too, so a policy reload
+/// on one node can't leave stale decisions cached on its peers.
+///
+/// `Cache::get` returns `Option<&V>` borrowed from `self`, which a bare
+/// network round trip can't satisfy, so `RedisCache` keeps a local
+/// `mirror` populated from Redis on lookup and returns a raw-pointer
+/// borrow into it. The pub/sub listener that must invalidate that
+/// mirror only holds a shared `Arc`, with no relationship to whatever
+/// lock (if any) serializes this instance's own `&mut self` calls, so
+/// letting it call `mirror.clear()` directly could free an entry out
+/// from under a borrow `get` already handed out. Instead the listener
+/// only bumps a shared `generation` counter, and the mirror is keyed by
+/// `(K, generation-at-insertion)`: a stale-generation entry is never
+/// overwritten or dropped by `get` (a `&self` method), only ever by
+/// `set`/`clear` (both `&mut self`), for which the borrow checker
+/// already forbids running while a `get`-derived borrow is alive — the
+/// same invariant this cache relied on before cross-node invalidation
+/// existed. Reclaiming memory for stale generations is therefore
+/// deferred to a later local `set` of the same key (which drops only
+/// that key's entries) or `clear` rather than happening the instant a
+/// peer invalidates.
+pub struct RedisCache<K, V>
+where
+    K: Eq + Hash + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    client: redis::Client,
+    key_prefix: String,
+    invalidation_channel: String,
+    ttl: Duration,
+    mirror: Mutex<HashMap<(K, u64), Box<V>>>,
+    generation: Arc<AtomicU64>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    inserts: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl<K, V> RedisCache<K, V>
+where
+    K: Eq + Hash + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    V: Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    /// Connects to `redis_url` and subscribes to `invalidation_channel`
+    /// so a peer's `clear()` invalidates this instance's mirror too.
+    /// `key_prefix` namespaces keys in the shared Redis keyspace so
+    /// multiple cached enforcers can share one Redis instance.
+    pub fn new(
+        redis_url: &str,
+        key_prefix: &str,
+        invalidation_channel: &str,
+    ) -> redis::RedisResult<Box<dyn Cache<K, V>>> {
+        let client = redis::Client::open(redis_url)?;
+        let generation = Arc::new(AtomicU64::new(0));
+        Self::subscribe_invalidations(client.clone(), invalidation_channel.to_string(), generation.clone());
+
+        Ok(Box::new(RedisCache {
+            client,
+            key_prefix: key_prefix.to_string(),
+            invalidation_channel: invalidation_channel.to_string(),
+            ttl: Duration::from_secs(120),
+            mirror: Mutex::new(HashMap::new()),
+            generation,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            inserts: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }) as Box<dyn Cache<K, V>>)
+    }
+
+    fn redis_key(&self, k: &K) -> redis::RedisResult<String> {
+        let encoded = bincode::serialize(k).map_err(|e| {
+            redis::RedisError::from((redis::ErrorKind::TypeError, "bincode encode failed", e.to_string()))
+        })?;
+        Ok(format!("{}:{}", self.key_prefix, base64::encode(encoded)))
+    }
+
+    /// Bumps `generation` whenever a peer publishes on
+    /// `invalidation_channel`, so every lookup on this node treats
+    /// mirror entries inserted under an older generation as a miss and
+    /// re-fetches from Redis. Never touches the mirror itself — see the
+    /// struct doc comment for why.
+    ///
+    /// Runs for the life of the process, reconnecting with exponential
+    /// backoff whenever the connection, subscription, or the pub/sub
+    /// stream itself drops — this channel is the only thing that keeps
+    /// this node's cache coherent with its peers, so silently giving up
+    /// after one failure would leave it serving stale decisions forever
+    /// with no way for an operator to notice.
+    fn subscribe_invalidations(client: redis::Client, channel: String, generation: Arc<AtomicU64>) {
+        async_std::task::spawn(async move {
+            const MIN_BACKOFF: Duration = Duration::from_millis(200);
+            const MAX_BACKOFF: Duration = Duration::from_secs(30);
+            let mut backoff = MIN_BACKOFF;
+
+            loop {
+                let conn = match client.get_async_connection().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        log::warn!(
+                            "redis cache: failed to connect for invalidation subscription on {channel}: {e}; retrying in {backoff:?}"
+                        );
+                        async_std::task::sleep(backoff).await;
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                        continue;
+                    }
+                };
+                let mut pubsub = conn.into_pubsub();
+                if let Err(e) = pubsub.subscribe(&channel).await {
+                    log::warn!(
+                        "redis cache: failed to subscribe to invalidation channel {channel}: {e}; retrying in {backoff:?}"
+                    );
+                    async_std::task::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    continue;
+                }
+
+                backoff = MIN_BACKOFF;
+                let mut stream = pubsub.on_message();
+                while stream.next().await.is_some() {
+                    generation.fetch_add(1, Ordering::SeqCst);
+                }
+
+                log::warn!(
+                    "redis cache: invalidation subscription to {channel} dropped; reconnecting in {backoff:?}"
+                );
+                async_std::task::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl<K, V> Cache<K, V> for RedisCache<K, V>
+where
+    K: Eq + Hash + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    V: Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    fn set_capacity(&mut self, _cap: usize) {
+        // Redis isn't bounded the way the in-memory backends are;
+        // configure `maxmemory`/`maxmemory-policy` on the Redis server
+        // for eviction under memory pressure instead.
+    }
+
+    fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+
+    async fn get<'a>(&'a self, k: &K) -> Option<&'a V> {
+        let generation = self.generation.load(Ordering::SeqCst);
+
+        if let Some(boxed) = self.mirror.lock().unwrap().get(&(k.clone(), generation)) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(unsafe { &*(boxed.as_ref() as *const V) });
+        }
+
+        let Ok(key) = self.redis_key(k) else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        let Ok(mut conn) = self.client.get_async_connection().await else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        let raw: Option<Vec<u8>> = match conn.get(&key).await {
+            Ok(raw) => raw,
+            Err(_) => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+        let Some(bytes) = raw else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        let Ok(v) = bincode::deserialize::<V>(&bytes) else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        let mut mirror = self.mirror.lock().unwrap();
+        let boxed = mirror
+            .entry((k.clone(), generation))
+            .or_insert_with(|| Box::new(v));
+        Some(unsafe { &*(boxed.as_ref() as *const V) })
+    }
+
+    async fn has(&self, k: &K) -> bool {
+        let generation = self.generation.load(Ordering::SeqCst);
+        if self.mirror.lock().unwrap().contains_key(&(k.clone(), generation)) {
+            return true;
+        }
+        let key = match self.redis_key(k) {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+        let mut conn = match self.client.get_async_connection().await {
+            Ok(conn) => conn,
+            Err(_) => return false,
+        };
+        conn.exists(&key).await.unwrap_or(false)
+    }
+
+    async fn set(&mut self, k: K, v: V) {
+        self.inserts.fetch_add(1, Ordering::Relaxed);
+        if let Ok(key) = self.redis_key(&k) {
+            if let Ok(bytes) = bincode::serialize(&v) {
+                if let Ok(mut conn) = self.client.get_async_connection().await {
+                    let _: redis::RedisResult<()> =
+                        conn.set_ex(&key, bytes, self.ttl.as_secs().max(1) as usize).await;
+                }
+            }
+        }
+
+        let generation = self.generation.load(Ordering::SeqCst);
+        let mut mirror = self.mirror.lock().unwrap();
+        // We have exclusive access here, so it's safe to reclaim any
+        // stale-generation entries left behind for this key by a past
+        // invalidation, instead of letting them accumulate forever.
+        mirror.retain(|(key, _), _| key != &k);
+        mirror.insert((k, generation), Box::new(v));
+    }
+
+    async fn clear(&mut self) {
+        self.mirror.lock().unwrap().clear();
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+
+        if let Ok(mut conn) = self.client.get_async_connection().await {
+            let pattern = format!("{}:*", self.key_prefix);
+            // SCAN instead of KEYS: KEYS is an O(N) blocking scan of the
+            // whole keyspace on Redis's single-threaded event loop, which
+            // would stall every other client sharing this Redis instance
+            // on every load_policy/update_policy-triggered clear().
+            let mut keys_to_delete: Vec<String> = Vec::new();
+            if let Ok(mut iter) = conn.scan_match::<_, String>(&pattern).await {
+                while let Some(key) = iter.next().await {
+                    keys_to_delete.push(key);
+                }
+            }
+            if !keys_to_delete.is_empty() {
+                let _: redis::RedisResult<()> = conn.del(keys_to_delete).await;
+            }
+            let _: redis::RedisResult<()> = conn.publish(&self.invalidation_channel, "clear").await;
+        }
+    }
+
+    async fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            inserts: self.inserts.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task;
+
+    // `RedisCache::new` only ever hands back a `Box<dyn Cache<K, V>>`,
+    // which erases `redis_key` — construct the concrete type directly
+    // to test it, skipping the invalidation-subscriber task `new` spawns
+    // since these tests don't exercise pub/sub.
+    fn unreachable_cache() -> RedisCache<Vec<&'static str>, bool> {
+        RedisCache {
+            client: redis::Client::open("redis://127.0.0.1:1").unwrap(),
+            key_prefix: "enforce".to_string(),
+            invalidation_channel: "enforce-invalidate".to_string(),
+            ttl: Duration::from_secs(120),
+            mirror: Mutex::new(HashMap::new()),
+            generation: Arc::new(AtomicU64::new(0)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            inserts: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    #[test]
+    fn test_redis_key_is_namespaced_and_deterministic() {
+        let cache = unreachable_cache();
+        let key = cache
+            .redis_key(&vec!["alice", "/data1", "read"])
+            .unwrap();
+
+        assert!(key.starts_with("enforce:"));
+        assert_eq!(
+            key,
+            cache.redis_key(&vec!["alice", "/data1", "read"]).unwrap()
+        );
+        assert_ne!(
+            key,
+            cache.redis_key(&vec!["bob", "/data2", "write"]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_has_set_against_unreachable_redis() {
+        let mut cache: Box<dyn Cache<Vec<&str>, bool>> =
+            RedisCache::new("redis://127.0.0.1:1", "test", "test-invalidate").unwrap();
+
+        task::block_on(async move {
+            assert_eq!(cache.get(&vec!["alice", "/data1", "read"]).await, None);
+            assert!(!cache.has(&vec!["alice", "/data1", "read"]).await);
+            assert_eq!(cache.stats().await.misses, 1);
+
+            // set() swallows the write-through failure (Redis is
+            // unreachable) and still updates the local mirror, so a
+            // value set while Redis is down is still served back from
+            // this instance.
+            cache.set(vec!["alice", "/data1", "read"], true).await;
+            assert_eq!(
+                cache.get(&vec!["alice", "/data1", "read"]).await,
+                Some(&true)
+            );
+
+            let stats = cache.stats().await;
+            assert_eq!(stats.misses, 1);
+            assert_eq!(stats.hits, 1);
+        });
+    }
+}
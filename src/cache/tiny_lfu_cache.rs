@@ -0,0 +1,425 @@
+use crate::cache::{Cache, CacheStats};
+
+use async_trait::async_trait;
+use rand::seq::IteratorRandom;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const SKETCH_DEPTH: usize = 4;
+const SAMPLE_SIZE: usize = 5;
+
+/// Count-Min Sketch of 4-bit saturating counters approximating each
+/// key's access frequency in bounded space. `increment` ages the whole
+/// sketch (halves every counter) once `sample_size` increments have
+/// accumulated, so stale popularity decays instead of pinning an
+/// early-hot key forever.
+struct CountMinSketch {
+    width: usize,
+    rows: Vec<Vec<u8>>,
+    additions: u64,
+    sample_size: u64,
+}
+
+impl CountMinSketch {
+    fn new(width: usize) -> Self {
+        let width = width.max(16);
+        CountMinSketch {
+            width,
+            rows: vec![vec![0u8; width]; SKETCH_DEPTH],
+            additions: 0,
+            sample_size: width as u64 * 10,
+        }
+    }
+
+    fn hash_of<K: Hash>(k: &K) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        k.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn slot(&self, row: usize, hash: u64) -> usize {
+        let mixed = hash ^ (row as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        (mixed as usize) % self.width
+    }
+
+    /// Returns whether this call triggered an aging pass, so callers that
+    /// keep auxiliary state in step with the sketch's reset cycle (e.g.
+    /// `FrequencySketch`'s doorkeeper) know when to reset too.
+    fn increment<K: Hash>(&mut self, k: &K) -> bool {
+        let hash = Self::hash_of(k);
+        for row in 0..SKETCH_DEPTH {
+            let slot = self.slot(row, hash);
+            if self.rows[row][slot] < 15 {
+                self.rows[row][slot] += 1;
+            }
+        }
+        self.additions += 1;
+        if self.additions >= self.sample_size {
+            self.age();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn estimate<K: Hash>(&self, k: &K) -> u8 {
+        let hash = Self::hash_of(k);
+        (0..SKETCH_DEPTH)
+            .map(|row| self.rows[row][self.slot(row, hash)])
+            .min()
+            .unwrap_or(0)
+    }
+
+    fn age(&mut self) {
+        for row in self.rows.iter_mut() {
+            for counter in row.iter_mut() {
+                *counter >>= 1;
+            }
+        }
+        self.additions /= 2;
+    }
+
+    fn clear(&mut self) {
+        for row in self.rows.iter_mut() {
+            row.iter_mut().for_each(|c| *c = 0);
+        }
+        self.additions = 0;
+    }
+}
+
+/// Bloom filter that tracks whether a key has been seen before. TinyLFU
+/// only starts counting a key in the `CountMinSketch` once it reappears,
+/// so one-hit-wonders don't spend sketch space. Reset alongside the
+/// `CountMinSketch`'s own aging cycle (see `FrequencySketch::record_access`)
+/// rather than only on `Cache::clear()` — otherwise a long-running cache
+/// that sees more distinct keys than `width` saturates the bitset toward
+/// all-`true`, and `contains()` starts reporting brand-new keys as repeat
+/// visitors, defeating the one-hit-wonder filter this exists for.
+struct Doorkeeper {
+    bits: Vec<bool>,
+}
+
+impl Doorkeeper {
+    fn new(size: usize) -> Self {
+        Doorkeeper {
+            bits: vec![false; size.max(16)],
+        }
+    }
+
+    fn indices<K: Hash>(&self, k: &K) -> (usize, usize) {
+        let mut first = DefaultHasher::new();
+        1u64.hash(&mut first);
+        k.hash(&mut first);
+        let mut second = DefaultHasher::new();
+        2u64.hash(&mut second);
+        k.hash(&mut second);
+        (
+            (first.finish() as usize) % self.bits.len(),
+            (second.finish() as usize) % self.bits.len(),
+        )
+    }
+
+    fn contains<K: Hash>(&self, k: &K) -> bool {
+        let (i, j) = self.indices(k);
+        self.bits[i] && self.bits[j]
+    }
+
+    fn insert<K: Hash>(&mut self, k: &K) {
+        let (i, j) = self.indices(k);
+        self.bits[i] = true;
+        self.bits[j] = true;
+    }
+
+    fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|b| *b = false);
+    }
+}
+
+/// TinyLFU admission frequency estimator: a doorkeeper for first
+/// sightings backed by a Count-Min Sketch for repeat visitors.
+struct FrequencySketch {
+    cms: CountMinSketch,
+    doorkeeper: Doorkeeper,
+}
+
+impl FrequencySketch {
+    fn new(cap: usize) -> Self {
+        let width = (cap.max(16) * 8).next_power_of_two();
+        FrequencySketch {
+            cms: CountMinSketch::new(width),
+            doorkeeper: Doorkeeper::new(width),
+        }
+    }
+
+    fn record_access<K: Hash>(&mut self, k: &K) {
+        if self.doorkeeper.contains(k) {
+            if self.cms.increment(k) {
+                self.doorkeeper.clear();
+            }
+        } else {
+            self.doorkeeper.insert(k);
+        }
+    }
+
+    fn estimate<K: Hash>(&self, k: &K) -> u64 {
+        self.cms.estimate(k) as u64 + u64::from(self.doorkeeper.contains(k))
+    }
+
+    fn clear(&mut self) {
+        self.cms.clear();
+        self.doorkeeper.clear();
+    }
+}
+
+/// Ristretto/Stretto-style cache: a TinyLFU frequency estimate gates
+/// admission of new keys, and eviction is cost-based SampledLFU —
+/// victims are drawn from a small sample of resident keys rather than a
+/// full scan, so a few large values can displace several small ones
+/// without the cache tracking a strict LRU/LFU order for every entry.
+pub struct TinyLfuCache<K, V>
+where
+    K: Eq + Hash + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    ttl: Duration,
+    max_cost: u64,
+    current_cost: u64,
+    // Value, cost, and the instant the entry stops being fresh.
+    entries: HashMap<K, (V, u64, Instant)>,
+    sketch: Mutex<FrequencySketch>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    inserts: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl<K, V> TinyLfuCache<K, V>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    /// `max_cost` is the eviction budget; entries inserted via `set`
+    /// (rather than `set_with_cost`) count for a cost of 1, so this
+    /// behaves like a plain capacity when costs aren't used.
+    pub fn new(max_cost: u64) -> Box<dyn Cache<K, V>> {
+        Box::new(TinyLfuCache {
+            ttl: Duration::from_secs(120),
+            max_cost,
+            current_cost: 0,
+            entries: HashMap::new(),
+            sketch: Mutex::new(FrequencySketch::new(max_cost.max(1) as usize)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            inserts: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }) as Box<dyn Cache<K, V>>
+    }
+
+    /// Draws `SAMPLE_SIZE` residents at random (reservoir sampling over
+    /// `entries`'s iteration order) rather than always taking the same
+    /// fixed prefix of it. `HashMap` iteration order is arbitrary but
+    /// stable for the life of a given table layout, so always taking the
+    /// first `SAMPLE_SIZE` keys would pin eviction pressure on whichever
+    /// handful happens to land first and leave every other resident
+    /// permanently immune — and if that fixed prefix were hot, `make_room`
+    /// could never find a losing victim and would refuse admission of any
+    /// new item forever, even with colder data elsewhere in the cache.
+    fn sample_victim(&self) -> Option<K> {
+        let sketch = self.sketch.lock().unwrap();
+        self.entries
+            .keys()
+            .choose_multiple(&mut rand::thread_rng(), SAMPLE_SIZE)
+            .into_iter()
+            .min_by_key(|k| sketch.estimate(*k))
+            .cloned()
+    }
+
+    /// Makes room for `incoming_cost`, evicting the lowest-estimated-
+    /// frequency key from a sample of residents each round. Returns
+    /// `false` (admission refused) if the incoming key's own estimate
+    /// loses to a sampled victim's, matching TinyLFU's admission policy
+    /// of protecting hot residents from one-hit-wonders. Ties are
+    /// admitted rather than refused: the incoming key's own insertion
+    /// deliberately never records itself in the sketch (see
+    /// `set_with_cost`), so an unread newcomer and an unread resident
+    /// both estimate at the sketch's floor, and always rejecting that
+    /// tie would make the cache permanently refuse anything it hasn't
+    /// already read back at least once.
+    fn make_room(&mut self, incoming_key: &K, incoming_cost: u64) -> bool {
+        if incoming_cost > self.max_cost {
+            return false;
+        }
+
+        while self.current_cost + incoming_cost > self.max_cost {
+            let Some(victim_key) = self.sample_victim() else {
+                break;
+            };
+
+            let (victim_freq, incoming_freq) = {
+                let sketch = self.sketch.lock().unwrap();
+                (sketch.estimate(&victim_key), sketch.estimate(incoming_key))
+            };
+            if incoming_freq < victim_freq {
+                return false;
+            }
+
+            if let Some((_, cost, _)) = self.entries.remove(&victim_key) {
+                self.current_cost -= cost;
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        true
+    }
+}
+
+#[async_trait]
+impl<K, V> Cache<K, V> for TinyLfuCache<K, V>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    fn set_capacity(&mut self, cap: usize) {
+        self.max_cost = cap as u64;
+        while self.current_cost > self.max_cost {
+            let Some(victim_key) = self.sample_victim() else {
+                break;
+            };
+            if let Some((_, cost, _)) = self.entries.remove(&victim_key) {
+                self.current_cost -= cost;
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+
+    async fn get<'a>(&'a self, k: &K) -> Option<&'a V> {
+        let now = Instant::now();
+        let found = self
+            .entries
+            .get(k)
+            .filter(|(_, _, expires_at)| now < *expires_at)
+            .map(|(v, _, _)| v);
+        {
+            let mut sketch = self.sketch.lock().unwrap();
+            sketch.record_access(k);
+        }
+        if found.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        found
+    }
+
+    async fn has(&self, k: &K) -> bool {
+        let now = Instant::now();
+        self.entries
+            .get(k)
+            .is_some_and(|(_, _, expires_at)| now < *expires_at)
+    }
+
+    async fn set(&mut self, k: K, v: V) {
+        self.set_with_cost(k, v, 1).await;
+    }
+
+    /// Inserting never records an access in the `FrequencySketch`; the
+    /// incoming key's estimate comes only from earlier `get` calls (see
+    /// `make_room`'s doc comment), so a cold key can't inflate its own
+    /// estimate for the very comparison deciding whether it gets in. The prior entry (if any) is pulled out before `make_room`
+    /// runs, both so it can't be sampled as its own eviction victim and
+    /// so a rejected admission can restore it verbatim instead of
+    /// leaving the key dropped from the cache.
+    async fn set_with_cost(&mut self, k: K, v: V, cost: u64) {
+        self.inserts.fetch_add(1, Ordering::Relaxed);
+
+        let old_entry = self.entries.remove(&k);
+        if let Some((_, old_cost, _)) = &old_entry {
+            self.current_cost -= old_cost;
+        }
+
+        if !self.make_room(&k, cost) {
+            if let Some(old_entry) = old_entry {
+                self.current_cost += old_entry.1;
+                self.entries.insert(k, old_entry);
+            }
+            return;
+        }
+
+        self.current_cost += cost;
+        self.entries.insert(k, (v, cost, Instant::now() + self.ttl));
+    }
+
+    async fn clear(&mut self) {
+        self.entries.clear();
+        self.current_cost = 0;
+        self.sketch.lock().unwrap().clear();
+    }
+
+    async fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            inserts: self.inserts.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task;
+
+    #[test]
+    fn test_set_and_get() {
+        let mut cache = TinyLfuCache::new(10);
+
+        task::block_on(async move {
+            cache.set(vec!["alice", "/data1", "read"], false).await;
+            assert!(cache.get(&vec!["alice", "/data1", "read"]).await == Some(&false));
+        });
+    }
+
+    #[test]
+    fn test_cost_based_eviction_fits_incoming_item() {
+        let mut cache = TinyLfuCache::new(5);
+
+        task::block_on(async move {
+            cache
+                .set_with_cost(vec!["alice", "/data1", "read"], false, 2)
+                .await;
+            cache
+                .set_with_cost(vec!["bob", "/data2", "write"], false, 2)
+                .await;
+            cache
+                .set_with_cost(vec!["carol", "/data3", "read_write"], false, 4)
+                .await;
+
+            assert!(cache.has(&vec!["carol", "/data3", "read_write"]).await);
+        });
+    }
+
+    #[test]
+    fn test_admission_protects_hot_resident() {
+        let mut cache = TinyLfuCache::new(1);
+
+        task::block_on(async move {
+            cache.set(vec!["alice", "/data1", "read"], false).await;
+            for _ in 0..10 {
+                assert!(cache.get(&vec!["alice", "/data1", "read"]).await.is_some());
+            }
+            // A brand-new, never-seen key shouldn't be able to evict a
+            // resident this hot on its very first insertion attempt.
+            cache.set(vec!["bob", "/data2", "write"], false).await;
+            assert!(cache.has(&vec!["alice", "/data1", "read"]).await);
+        });
+    }
+}